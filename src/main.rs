@@ -1,156 +1,514 @@
 use ggez::conf::{WindowMode, WindowSetup};
+use ggez::input::mouse::MouseButton;
 use ggez::{event, graphics, Context, ContextBuilder, GameResult};
-use std::ops::{Add, Div, Mul, Sub};
+use ggez_egui::{egui, EguiBackend};
+use glam::{Vec2, Vec3};
+use rayon::prelude::*;
 
 use ggez::mint;
 
-const G: f32 = 1.0;
+use std::sync::mpsc;
+use std::thread;
 
-#[derive(Debug, Clone, Copy)]
-struct Vec2 {
-    x: f32,
-    y: f32,
-}
+mod search;
 
-impl Vec2 {
-    fn new(x: f32, y: f32) -> Self {
-        Vec2 { x, y }
-    }
-    fn magnitude(self) -> f32 {
-        (self.x * self.x + self.y * self.y).sqrt()
-    }
-    fn normalize(self) -> Self {
-        let mag = self.magnitude();
-        if mag == 0.0 {
+const DEFAULT_G: f32 = 1.0;
+const DEFAULT_SPEED_FACTOR: f32 = 500.0;
+const BODY_RADIUS: f32 = 5.0;
+const NEW_BODY_MASS: f32 = 20.0;
+/// Lower values leave a longer-lived fading trail.
+const TRAIL_FADE_ALPHA: f32 = 0.04;
+const TRAIL_DOT_RADIUS: f32 = 1.5;
+/// Camera distance from the z=0 plane, for `Vec3`'s perspective projection.
+const CAMERA_DISTANCE: f32 = 600.0;
+
+/// Abstracts `compute_accelerations`/`step` over `glam::Vec2` and `Vec3`.
+trait SimVec:
+    Copy
+    + Send
+    + Sync
+    + 'static
+    + std::ops::Add<Output = Self>
+    + std::ops::Sub<Output = Self>
+    + std::ops::Mul<f32, Output = Self>
+    + std::ops::Div<f32, Output = Self>
+{
+    const ZERO: Self;
+
+    fn length(self) -> f32;
+
+    fn normalize_or_zero(self) -> Self {
+        let len = self.length();
+        if len == 0.0 {
             self
         } else {
-            self / mag
+            self * (1.0 / len)
         }
     }
-}
 
-impl Add for Vec2 {
-    type Output = Vec2;
-    fn add(self, other: Vec2) -> Vec2 {
-        Vec2 {
-            x: self.x + other.x,
-            y: self.y + other.y,
-        }
+    /// Lifts a point on the camera's render plane into this dimension.
+    fn from_plane(xy: Vec2) -> Self;
+
+    /// Projects down to the camera's 2D render plane, applying perspective
+    /// for any axis beyond the screen's two.
+    fn project(self) -> Vec2;
+
+    /// Replaces the render-plane components with `xy`, preserving any other
+    /// axis (used when the mouse drags a body).
+    fn with_plane(self, xy: Vec2) -> Self;
+
+    /// Nudges a copy along any axis beyond the render plane.
+    fn with_out_of_plane_offset(self, _offset: f32) -> Self {
+        self
     }
 }
-impl Sub for Vec2 {
-    type Output = Vec2;
-    fn sub(self, other: Vec2) -> Vec2 {
-        Vec2 {
-            x: self.x - other.x,
-            y: self.y - other.y,
-        }
+
+impl SimVec for Vec2 {
+    const ZERO: Self = Vec2::ZERO;
+
+    fn length(self) -> f32 {
+        Vec2::length(self)
+    }
+
+    fn from_plane(xy: Vec2) -> Self {
+        xy
+    }
+
+    fn project(self) -> Vec2 {
+        self
+    }
+
+    fn with_plane(self, xy: Vec2) -> Self {
+        xy
     }
 }
-impl Mul<f32> for Vec2 {
-    type Output = Vec2;
-    fn mul(self, scalar: f32) -> Vec2 {
-        Vec2 {
-            x: self.x * scalar,
-            y: self.y * scalar,
-        }
+
+impl SimVec for Vec3 {
+    const ZERO: Self = Vec3::ZERO;
+
+    fn length(self) -> f32 {
+        Vec3::length(self)
+    }
+
+    fn from_plane(xy: Vec2) -> Self {
+        Vec3::new(xy.x, xy.y, 0.0)
+    }
+
+    fn project(self) -> Vec2 {
+        let depth = (CAMERA_DISTANCE + self.z).max(1.0);
+        Vec2::new(self.x, self.y) * (CAMERA_DISTANCE / depth)
+    }
+
+    fn with_plane(self, xy: Vec2) -> Self {
+        Vec3::new(xy.x, xy.y, self.z)
+    }
+
+    fn with_out_of_plane_offset(self, offset: f32) -> Self {
+        self + Vec3::new(0.0, 0.0, offset)
     }
 }
-impl Div<f32> for Vec2 {
-    type Output = Vec2;
-    fn div(self, scalar: f32) -> Vec2 {
-        Vec2 {
-            x: self.x / scalar,
-            y: self.y / scalar,
+
+/// Maps the camera's 2D render plane to screen space and back.
+struct Camera {
+    center: Vec2,
+    zoom: f32,
+    auto_frame: bool,
+}
+
+impl Camera {
+    fn new() -> Self {
+        Camera {
+            center: Vec2::ZERO,
+            zoom: 1.0,
+            auto_frame: false,
         }
     }
+
+    fn world_to_screen(&self, world: Vec2, screen_w: f32, screen_h: f32) -> Vec2 {
+        Vec2::new(
+            (world.x - self.center.x) * self.zoom + screen_w / 2.0,
+            (world.y - self.center.y) * self.zoom + screen_h / 2.0,
+        )
+    }
+
+    fn screen_to_world(&self, screen: Vec2, screen_w: f32, screen_h: f32) -> Vec2 {
+        Vec2::new(
+            (screen.x - screen_w / 2.0) / self.zoom + self.center.x,
+            (screen.y - screen_h / 2.0) / self.zoom + self.center.y,
+        )
+    }
 }
 
 #[derive(Debug, Clone)]
-struct Body {
-    pos: Vec2,
-    vel: Vec2,
+struct Body<V> {
+    pos: V,
+    vel: V,
     mass: f32,
     color: graphics::Color,
-    trail: Vec<Vec2>,
 }
 
-struct Simulation {
-    bodies: Vec<Body>,
+/// What the mouse is currently doing to the simulation.
+enum DragState {
+    /// Repositioning an existing body while the button is held.
+    MoveBody { index: usize },
+    /// Dragging out a velocity vector for a body that was just spawned.
+    Launch {
+        body_index: usize,
+        origin: Vec2,
+        current: Vec2,
+    },
+}
+
+struct Simulation<V> {
+    bodies: Vec<Body<V>>,
     dt: f32,
     accumulator: f32,
+    hovered: Option<usize>,
+    drag: Option<DragState>,
+    g: f32,
+    speed_factor: f32,
+    paused: bool,
+    step_once: bool,
+    egui_backend: EguiBackend,
+    camera: Camera,
+    mouse_screen_pos: Vec2,
+    panning: bool,
+    trail_canvas: graphics::ScreenImage,
+    trail_canvas_initialized: bool,
+    /// Write buffer for `step`; swapped with `bodies` after each step.
+    back_buffer: Vec<Body<V>>,
+    search_iterations: u32,
+    last_search_score: Option<f32>,
+    /// `Some` while `hill_climb` is running on its background thread.
+    search_in_progress: Option<mpsc::Receiver<search::SearchResult<V>>>,
 }
 
-impl Simulation {
-    fn new() -> Self {
+impl<V: SimVec> Simulation<V> {
+    fn initial_bodies() -> Vec<Body<V>> {
+        vec![
+            Body {
+                pos: V::from_plane(Vec2::new(-100.0, 0.0)).with_out_of_plane_offset(20.0),
+                vel: V::from_plane(Vec2::new(0.0, 0.5)),
+                mass: 70.0,
+                color: graphics::Color::from_rgb(255, 0, 0),
+            },
+            Body {
+                pos: V::from_plane(Vec2::new(0.0, 0.0)),
+                vel: V::from_plane(Vec2::new(0.0, 0.0)),
+                mass: 100.0,
+                color: graphics::Color::from_rgb(0, 255, 0),
+            },
+            Body {
+                pos: V::from_plane(Vec2::new(100.0, 0.0)).with_out_of_plane_offset(-20.0),
+                vel: V::from_plane(Vec2::new(0.0, -0.50)),
+                mass: 30.0,
+                color: graphics::Color::from_rgb(0, 0, 255),
+            },
+        ]
+    }
+
+    fn new(ctx: &mut Context) -> Self {
         Simulation {
-            bodies: vec![
-                Body {
-                    pos: Vec2::new(-100.0, 0.0),
-                    vel: Vec2::new(0.0, 0.5),
-                    mass: 70.0,
-                    color: graphics::Color::from_rgb(255, 0, 0),
-                    trail: Vec::new(),
-                },
-                Body {
-                    pos: Vec2::new(0.0, 0.0),
-                    vel: Vec2::new(0.0, 0.0),
-                    mass: 100.0,
-                    color: graphics::Color::from_rgb(0, 255, 0),
-                    trail: Vec::new(),
-                },
-                Body {
-                    pos: Vec2::new(100.0, 0.0),
-                    vel: Vec2::new(0.0, -0.50),
-                    mass: 30.0,
-                    color: graphics::Color::from_rgb(0, 0, 255),
-                    trail: Vec::new(),
-                },
-            ],
+            bodies: Self::initial_bodies(),
             dt: 0.01,
             accumulator: 0.0,
+            hovered: None,
+            drag: None,
+            g: DEFAULT_G,
+            speed_factor: DEFAULT_SPEED_FACTOR,
+            paused: false,
+            step_once: false,
+            egui_backend: EguiBackend::new(ctx),
+            camera: Camera::new(),
+            mouse_screen_pos: Vec2::ZERO,
+            panning: false,
+            trail_canvas: graphics::ScreenImage::new(ctx, None, 1.0, 1.0, 1),
+            trail_canvas_initialized: false,
+            back_buffer: Vec::new(),
+            search_iterations: 500,
+            last_search_score: None,
+            search_in_progress: None,
+        }
+    }
+
+    /// Centers the camera on the bodies' mass-weighted centroid and sets the
+    /// zoom so that their bounding box's width and height fit the screen.
+    /// The centroid need not be the box's center, so a body far from the
+    /// centroid can still fall outside the screen.
+    fn auto_frame(&mut self, screen_w: f32, screen_h: f32) {
+        if self.bodies.is_empty() {
+            return;
+        }
+
+        let total_mass: f32 = self.bodies.iter().map(|b| b.mass).sum();
+        let centroid = self
+            .bodies
+            .iter()
+            .fold(Vec2::ZERO, |acc, b| acc + b.pos.project() * b.mass)
+            / total_mass.max(0.001);
+        self.camera.center = centroid;
+
+        let mut min = Vec2::new(f32::MAX, f32::MAX);
+        let mut max = Vec2::new(f32::MIN, f32::MIN);
+        for body in &self.bodies {
+            let p = body.pos.project();
+            min.x = min.x.min(p.x);
+            min.y = min.y.min(p.y);
+            max.x = max.x.max(p.x);
+            max.y = max.y.max(p.y);
         }
+
+        const PADDING: f32 = 1.2;
+        let width = (max.x - min.x).max(1.0) * PADDING;
+        let height = (max.y - min.y).max(1.0) * PADDING;
+        let zoom = (screen_w / width).min(screen_h / height);
+        self.camera.zoom = zoom.clamp(0.02, 20.0);
     }
 
-    fn compute_accelerations(&self) -> Vec<Vec2> {
+    /// Zooms the camera by `factor` while keeping the point under
+    /// `cursor_screen` fixed on screen.
+    fn zoom_at(&mut self, cursor_screen: Vec2, factor: f32, screen_w: f32, screen_h: f32) {
+        let world_before = self
+            .camera
+            .screen_to_world(cursor_screen, screen_w, screen_h);
+        self.camera.zoom = (self.camera.zoom * factor).clamp(0.02, 20.0);
+        let world_after = self
+            .camera
+            .screen_to_world(cursor_screen, screen_w, screen_h);
+        self.camera.center += world_before - world_after;
+    }
+
+    /// Returns the system's total kinetic and potential energy.
+    fn total_energy(&self) -> (f32, f32) {
+        let kinetic: f32 = self
+            .bodies
+            .iter()
+            .map(|b| 0.5 * b.mass * b.vel.length().powi(2))
+            .sum();
+
         let n = self.bodies.len();
-        let mut acc = vec![Vec2::new(0.0, 0.0); n];
-        for (i, acc_i) in acc.iter_mut().enumerate() {
-            for j in 0..n {
-                if i == j {
-                    continue;
-                }
-                let diff = self.bodies[j].pos - self.bodies[i].pos;
-                let distance = diff.magnitude().max(0.1); // Softening to prevent division by zero
-                *acc_i =
-                    *acc_i + diff.normalize() * (G * self.bodies[j].mass / (distance * distance));
+        let mut potential = 0.0;
+        for i in 0..n {
+            for j in (i + 1)..n {
+                let distance = (self.bodies[j].pos - self.bodies[i].pos).length().max(0.1);
+                potential -= self.g * self.bodies[i].mass * self.bodies[j].mass / distance;
             }
         }
-        acc
+        (kinetic, potential)
     }
 
-    fn step(&mut self) {
-        let acc_old = self.compute_accelerations();
-        let dt = self.dt;
+    /// Converts a screen-space point into a point on the camera's render plane.
+    fn screen_to_world(&self, ctx: &Context, screen_pos: Vec2) -> Vec2 {
+        let (screen_w, screen_h) = ctx.gfx.drawable_size();
+        self.camera.screen_to_world(screen_pos, screen_w, screen_h)
+    }
 
-        for (i, body) in self.bodies.iter_mut().enumerate() {
-            body.pos = body.pos + body.vel * dt + acc_old[i] * (0.5 * dt * dt);
+    /// Returns the index of the body nearest `screen_pos`, if `screen_pos`
+    /// falls within that body's on-screen draw radius.
+    fn pick_body(&self, screen_pos: Vec2, screen_w: f32, screen_h: f32) -> Option<usize> {
+        self.bodies
+            .iter()
+            .enumerate()
+            .map(|(i, body)| {
+                let body_screen =
+                    self.camera
+                        .world_to_screen(body.pos.project(), screen_w, screen_h);
+                (i, (body_screen - screen_pos).length())
+            })
+            .filter(|(_, dist)| *dist <= BODY_RADIUS)
+            .min_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Greater))
+            .map(|(i, _)| i)
+    }
+
+    /// Computes the gravitational acceleration on every body in parallel,
+    /// from an immutable snapshot of the system.
+    fn compute_accelerations(bodies: &[Body<V>], g: f32) -> Vec<V> {
+        bodies
+            .par_iter()
+            .enumerate()
+            .map(|(i, body_i)| {
+                let mut acc = V::ZERO;
+                for (j, body_j) in bodies.iter().enumerate() {
+                    if i == j {
+                        continue;
+                    }
+                    let diff = body_j.pos - body_i.pos;
+                    let distance = diff.length().max(0.1); // Softening to prevent division by zero
+                    acc =
+                        acc + diff.normalize_or_zero() * (g * body_j.mass / (distance * distance));
+                }
+                acc
+            })
+            .collect()
+    }
+
+    /// Reads `front`, writes the next-frame state into `back`, and leaves the
+    /// caller to swap the two.
+    fn integrate_step(front: &[Body<V>], back: &mut Vec<Body<V>>, dt: f32, g: f32) {
+        let acc_old = Self::compute_accelerations(front, g);
+
+        if back.len() != front.len() {
+            *back = front.to_vec();
+        }
+        for (i, body) in front.iter().enumerate() {
+            let mut next = body.clone();
+            next.pos = body.pos + body.vel * dt + acc_old[i] * (0.5 * dt * dt);
+            back[i] = next;
         }
 
-        let acc_new = self.compute_accelerations();
-        for (i, body) in self.bodies.iter_mut().enumerate() {
-            body.vel = body.vel + (acc_old[i] + acc_new[i]) * (0.5 * dt);
-            let pos = body.pos;
-            body.trail.push(pos);
+        let acc_new = Self::compute_accelerations(back, g);
+        for i in 0..back.len() {
+            back[i].vel = front[i].vel + (acc_old[i] + acc_new[i]) * (0.5 * dt);
         }
     }
+
+    fn step(&mut self) {
+        Self::integrate_step(&self.bodies, &mut self.back_buffer, self.dt, self.g);
+        std::mem::swap(&mut self.bodies, &mut self.back_buffer);
+    }
+
+    /// Replaces the live body set, clearing transient per-run state.
+    fn load_bodies(&mut self, bodies: Vec<Body<V>>) {
+        self.bodies = bodies;
+        self.back_buffer.clear();
+        self.accumulator = 0.0;
+        self.hovered = None;
+        self.drag = None;
+    }
+
+    /// Kicks off `hill_climb` on a background thread; `update` polls
+    /// `search_in_progress` for the result.
+    fn start_search(&mut self) {
+        let config = search::SearchConfig {
+            dt: self.dt,
+            g: self.g,
+            ..Default::default()
+        };
+        let iterations = self.search_iterations;
+        let (tx, rx) = mpsc::channel();
+        thread::spawn(move || {
+            let _ = tx.send(search::hill_climb::<V>(&config, iterations));
+        });
+        self.search_in_progress = Some(rx);
+    }
+
+    /// Builds the egui control panel for this frame and applies any
+    /// Pause/Single-Step/Reset/Add/Remove actions the user triggered.
+    fn build_ui(&mut self) {
+        let egui_ctx = self.egui_backend.ctx();
+        egui::Window::new("Simulation controls").show(&egui_ctx, |ui| {
+            ui.add(egui::Slider::new(&mut self.g, 0.0..=10.0).text("G"));
+            ui.add(egui::Slider::new(&mut self.dt, 0.001..=0.05).text("dt"));
+            ui.add(egui::Slider::new(&mut self.speed_factor, 1.0..=2000.0).text("speed factor"));
+            ui.checkbox(&mut self.camera.auto_frame, "Auto-frame camera");
+
+            ui.horizontal(|ui| {
+                if ui
+                    .button(if self.paused { "Resume" } else { "Pause" })
+                    .clicked()
+                {
+                    self.paused = !self.paused;
+                }
+                if ui.button("Single-Step").clicked() {
+                    self.paused = true;
+                    self.step_once = true;
+                }
+                if ui.button("Reset").clicked() {
+                    self.bodies = Self::initial_bodies();
+                    self.accumulator = 0.0;
+                    self.camera = Camera::new();
+                }
+            });
+
+            ui.horizontal(|ui| {
+                if ui.button("Add body").clicked() {
+                    self.bodies.push(Body {
+                        pos: V::ZERO,
+                        vel: V::ZERO,
+                        mass: NEW_BODY_MASS,
+                        color: graphics::Color::from_rgb(255, 255, 0),
+                    });
+                }
+                if ui.button("Remove body").clicked() {
+                    self.bodies.pop();
+                    self.hovered = None;
+                    self.drag = None;
+                }
+            });
+
+            ui.separator();
+            for (i, body) in self.bodies.iter_mut().enumerate() {
+                ui.horizontal(|ui| {
+                    ui.label(format!("Body {i}"));
+                    ui.add(
+                        egui::DragValue::new(&mut body.mass)
+                            .prefix("mass: ")
+                            .speed(0.5)
+                            .clamp_range(0.1..=10_000.0),
+                    );
+                    let mut rgb = [body.color.r, body.color.g, body.color.b];
+                    if ui.color_edit_button_rgb(&mut rgb).changed() {
+                        body.color = graphics::Color::new(rgb[0], rgb[1], rgb[2], 1.0);
+                    }
+                });
+            }
+
+            ui.separator();
+            let (kinetic, potential) = self.total_energy();
+            ui.label(format!("Kinetic energy: {kinetic:.2}"));
+            ui.label(format!("Potential energy: {potential:.2}"));
+            ui.label(format!("Total energy: {:.2}", kinetic + potential));
+
+            ui.separator();
+            ui.label("Periodic orbit search");
+            ui.add(egui::Slider::new(&mut self.search_iterations, 50..=5000).text("iterations"));
+            let searching = self.search_in_progress.is_some();
+            ui.add_enabled_ui(!searching, |ui| {
+                if ui.button("Search for a stable orbit").clicked() {
+                    self.start_search();
+                }
+            });
+            if searching {
+                ui.label("Searching...");
+            }
+            if let Some(score) = self.last_search_score {
+                ui.label(format!("Best return distance found: {score:.4}"));
+            }
+        });
+    }
 }
 
-impl event::EventHandler for Simulation {
+impl<V: SimVec> event::EventHandler for Simulation<V> {
     fn update(&mut self, ctx: &mut Context) -> GameResult {
+        self.egui_backend.update(ctx);
+
+        if let Some(rx) = &self.search_in_progress {
+            if let Ok(result) = rx.try_recv() {
+                self.load_bodies(result.bodies);
+                self.last_search_score = Some(result.score);
+                self.search_in_progress = None;
+            }
+        }
+
+        self.build_ui();
+
+        let (screen_w, screen_h) = ctx.gfx.drawable_size();
+        if self.camera.auto_frame {
+            self.auto_frame(screen_w, screen_h);
+        }
+
+        if self.paused && !self.step_once {
+            return Ok(());
+        }
+
+        if self.step_once {
+            self.step();
+            self.step_once = false;
+            return Ok(());
+        }
+
         let delta = ctx.time.delta().as_secs_f32();
-        let speed_factor = 500.0;
-        self.accumulator += delta * speed_factor;
+        self.accumulator += delta * self.speed_factor;
         while self.accumulator >= self.dt {
             self.step();
             self.accumulator -= self.dt;
@@ -159,42 +517,203 @@ impl event::EventHandler for Simulation {
     }
 
     fn draw(&mut self, ctx: &mut Context) -> GameResult {
-        let mut canvas =
-            graphics::Canvas::from_frame(ctx, graphics::Color::from_rgb(255, 255, 255));
         let (screen_w, screen_h) = ctx.gfx.drawable_size();
+        let to_screen = |p: V| self.camera.world_to_screen(p.project(), screen_w, screen_h);
+
+        // Erode last frame's trail with a translucent quad, then stamp this
+        // frame's body positions on top.
+        let trail_image = self.trail_canvas.image(ctx);
+        let clear_color = if self.trail_canvas_initialized {
+            None
+        } else {
+            self.trail_canvas_initialized = true;
+            Some(graphics::Color::from_rgb(255, 255, 255))
+        };
+        let mut trail_canvas = graphics::Canvas::from_image(ctx, trail_image.clone(), clear_color);
+
+        let fade = graphics::Mesh::new_rectangle(
+            ctx,
+            graphics::DrawMode::fill(),
+            graphics::Rect::new(0.0, 0.0, screen_w, screen_h),
+            graphics::Color::new(1.0, 1.0, 1.0, TRAIL_FADE_ALPHA),
+        )?;
+        trail_canvas.draw(&fade, graphics::DrawParam::default());
 
         for body in &self.bodies {
-            if body.trail.len() > 1 {
-                let trail_points: Vec<mint::Point2<f32>> = body
-                    .trail
-                    .iter()
-                    .map(|p| mint::Point2 {
-                        x: p.x + screen_w / 2.0,
-                        y: p.y + screen_h / 2.0,
-                    })
-                    .collect();
-                let trail_line = graphics::Mesh::new_line(ctx, &trail_points, 1.0, body.color)?;
-                canvas.draw(&trail_line, graphics::DrawParam::default());
-            }
+            let s = to_screen(body.pos);
+            let dot = graphics::Mesh::new_circle(
+                ctx,
+                graphics::DrawMode::fill(),
+                mint::Point2 { x: s.x, y: s.y },
+                TRAIL_DOT_RADIUS,
+                0.1,
+                body.color,
+            )?;
+            trail_canvas.draw(&dot, graphics::DrawParam::default());
         }
+        trail_canvas.finish(ctx)?;
+
+        let mut canvas =
+            graphics::Canvas::from_frame(ctx, graphics::Color::from_rgb(255, 255, 255));
+        canvas.draw(&trail_image, graphics::DrawParam::default());
 
         for body in &self.bodies {
+            let s = to_screen(body.pos);
             let circle = graphics::Mesh::new_circle(
                 ctx,
                 graphics::DrawMode::fill(),
-                mint::Point2 {
-                    x: body.pos.x + screen_w / 2.0,
-                    y: body.pos.y + screen_h / 2.0,
-                },
-                5.0,
+                mint::Point2 { x: s.x, y: s.y },
+                BODY_RADIUS,
                 0.1,
                 body.color,
             )?;
             canvas.draw(&circle, graphics::DrawParam::default());
         }
 
+        if let Some(index) = self.hovered {
+            let s = to_screen(self.bodies[index].pos);
+            let outline = graphics::Mesh::new_circle(
+                ctx,
+                graphics::DrawMode::stroke(2.0),
+                mint::Point2 { x: s.x, y: s.y },
+                BODY_RADIUS + 3.0,
+                0.1,
+                graphics::Color::from_rgb(255, 255, 0),
+            )?;
+            canvas.draw(&outline, graphics::DrawParam::default());
+        }
+
+        if let Some(DragState::Launch {
+            origin, current, ..
+        }) = &self.drag
+        {
+            let from = self.camera.world_to_screen(*origin, screen_w, screen_h);
+            let to = self.camera.world_to_screen(*current, screen_w, screen_h);
+            let aim_line = graphics::Mesh::new_line(
+                ctx,
+                &[
+                    mint::Point2 {
+                        x: from.x,
+                        y: from.y,
+                    },
+                    mint::Point2 { x: to.x, y: to.y },
+                ],
+                2.0,
+                graphics::Color::from_rgb(255, 255, 0),
+            )?;
+            canvas.draw(&aim_line, graphics::DrawParam::default());
+        }
+
+        canvas.draw(&self.egui_backend, graphics::DrawParam::default());
+
         canvas.finish(ctx)
     }
+
+    fn mouse_button_down_event(
+        &mut self,
+        ctx: &mut Context,
+        button: MouseButton,
+        x: f32,
+        y: f32,
+    ) -> GameResult {
+        if self.egui_backend.ctx().is_pointer_over_area() {
+            return Ok(());
+        }
+
+        if button == MouseButton::Middle {
+            self.panning = true;
+            return Ok(());
+        }
+
+        if button != MouseButton::Left {
+            return Ok(());
+        }
+
+        let world_pos = self.screen_to_world(ctx, Vec2::new(x, y));
+        let (screen_w, screen_h) = ctx.gfx.drawable_size();
+        self.drag = match self.pick_body(Vec2::new(x, y), screen_w, screen_h) {
+            Some(index) => Some(DragState::MoveBody { index }),
+            None => {
+                self.bodies.push(Body {
+                    pos: V::from_plane(world_pos),
+                    vel: V::ZERO,
+                    mass: NEW_BODY_MASS,
+                    color: graphics::Color::from_rgb(255, 255, 0),
+                });
+                Some(DragState::Launch {
+                    body_index: self.bodies.len() - 1,
+                    origin: world_pos,
+                    current: world_pos,
+                })
+            }
+        };
+        Ok(())
+    }
+
+    fn mouse_button_up_event(
+        &mut self,
+        _ctx: &mut Context,
+        button: MouseButton,
+        _x: f32,
+        _y: f32,
+    ) -> GameResult {
+        if button == MouseButton::Left {
+            self.drag = None;
+        }
+        if button == MouseButton::Middle {
+            self.panning = false;
+        }
+        Ok(())
+    }
+
+    fn mouse_motion_event(
+        &mut self,
+        ctx: &mut Context,
+        x: f32,
+        y: f32,
+        dx: f32,
+        dy: f32,
+    ) -> GameResult {
+        self.mouse_screen_pos = Vec2::new(x, y);
+
+        if self.panning {
+            self.camera.center -= Vec2::new(dx, dy) / self.camera.zoom;
+            return Ok(());
+        }
+
+        let world_pos = self.screen_to_world(ctx, Vec2::new(x, y));
+        let (screen_w, screen_h) = ctx.gfx.drawable_size();
+        match self.drag {
+            Some(DragState::MoveBody { index }) => {
+                self.bodies[index].pos = self.bodies[index].pos.with_plane(world_pos);
+            }
+            Some(DragState::Launch {
+                body_index, origin, ..
+            }) => {
+                self.bodies[body_index].vel = V::from_plane((world_pos - origin) * 0.1);
+                self.drag = Some(DragState::Launch {
+                    body_index,
+                    origin,
+                    current: world_pos,
+                });
+            }
+            None => {
+                self.hovered = self.pick_body(Vec2::new(x, y), screen_w, screen_h);
+            }
+        }
+        Ok(())
+    }
+
+    fn mouse_wheel_event(&mut self, ctx: &mut Context, _x: f32, y: f32) -> GameResult {
+        self.egui_backend.input.mouse_wheel_event(_x, y);
+        if self.egui_backend.ctx().is_pointer_over_area() {
+            return Ok(());
+        }
+        let (screen_w, screen_h) = ctx.gfx.drawable_size();
+        let factor = if y > 0.0 { 1.1 } else { 0.9 };
+        self.zoom_at(self.mouse_screen_pos, factor, screen_w, screen_h);
+        Ok(())
+    }
 }
 
 pub fn main() -> GameResult {
@@ -209,11 +728,11 @@ pub fn main() -> GameResult {
         ..Default::default()
     };
 
-    let (ctx, event_loop) = ContextBuilder::new("three_body_simulation", "me")
+    let (mut ctx, event_loop) = ContextBuilder::new("three_body_simulation", "me")
         .window_mode(window_mode)
         .window_setup(window_setup)
         .build()?;
 
-    let simulation = Simulation::new();
+    let simulation = Simulation::<Vec3>::new(&mut ctx);
     event::run(ctx, event_loop, simulation)
 }
@@ -0,0 +1,204 @@
+//! Hill-climbing search for initial conditions that produce bounded,
+//! (near-)periodic three-body orbits.
+//!
+//! A candidate is the free positions/velocities of two bodies; the third is
+//! derived by holding the system's center of mass at rest. Candidates are
+//! always laid out on the camera's 2D render plane, so the search explores
+//! the same family regardless of whether the live simulation is 2D or 3D.
+
+use crate::{Body, SimVec, Simulation};
+use glam::Vec2;
+use rand::Rng;
+
+/// Free scalars per candidate: (pos, vel) for two of the three bodies.
+const PARAM_COUNT: usize = 8;
+
+const SEARCH_MASS: f32 = 100.0;
+const INITIAL_SIGMA: f32 = 40.0;
+const SIGMA_SHRINK: f32 = 0.995;
+/// Independent random restarts `hill_climb` runs.
+const RESTART_COUNT: u32 = 5;
+/// Softening length used by `compute_accelerations`; closer encounters are
+/// penalized rather than trusted.
+const SOFTENING_LENGTH: f32 = 0.1;
+const CLOSE_ENCOUNTER_PENALTY: f32 = 10.0;
+
+pub struct SearchConfig {
+    pub dt: f32,
+    pub g: f32,
+    pub horizon_steps: usize,
+    pub settle_steps: usize,
+    pub bounding_radius: f32,
+}
+
+impl Default for SearchConfig {
+    fn default() -> Self {
+        SearchConfig {
+            dt: 0.01,
+            g: 1.0,
+            horizon_steps: 4000,
+            settle_steps: 200,
+            bounding_radius: 1000.0,
+        }
+    }
+}
+
+pub struct SearchResult<V> {
+    pub bodies: Vec<Body<V>>,
+    pub score: f32,
+}
+
+/// Expands a candidate's free parameters into the three-body system.
+fn params_to_bodies<V: SimVec>(params: &[f32; PARAM_COUNT]) -> Vec<Body<V>> {
+    let pos0 = Vec2::new(params[0], params[1]);
+    let vel0 = Vec2::new(params[2], params[3]);
+    let pos1 = Vec2::new(params[4], params[5]);
+    let vel1 = Vec2::new(params[6], params[7]);
+
+    // Equal masses: fixing the center of mass at rest means body 2 is just
+    // the negative sum of the other two.
+    let pos2 = (pos0 + pos1) * -1.0;
+    let vel2 = (vel0 + vel1) * -1.0;
+
+    vec![
+        Body {
+            pos: V::from_plane(pos0),
+            vel: V::from_plane(vel0),
+            mass: SEARCH_MASS,
+            color: ggez::graphics::Color::from_rgb(255, 0, 0),
+        },
+        Body {
+            pos: V::from_plane(pos1),
+            vel: V::from_plane(vel1),
+            mass: SEARCH_MASS,
+            color: ggez::graphics::Color::from_rgb(0, 255, 0),
+        },
+        Body {
+            pos: V::from_plane(pos2),
+            vel: V::from_plane(vel2),
+            mass: SEARCH_MASS,
+            color: ggez::graphics::Color::from_rgb(0, 0, 255),
+        },
+    ]
+}
+
+/// Euclidean distance between two phase-space states.
+fn phase_distance<V: SimVec>(a: &[Body<V>], b: &[Body<V>]) -> f32 {
+    let mut total = 0.0;
+    for i in 0..a.len() {
+        total += (a[i].pos - b[i].pos).length().powi(2);
+        total += (a[i].vel - b[i].vel).length().powi(2);
+    }
+    total.sqrt()
+}
+
+/// Integrates `params` for `config.horizon_steps` and returns how close the
+/// system returns to its starting state, after an initial settling window.
+fn score<V: SimVec>(params: &[f32; PARAM_COUNT], config: &SearchConfig) -> f32 {
+    let initial_bodies = params_to_bodies::<V>(params);
+    let s0 = initial_bodies.clone();
+
+    let mut front = initial_bodies;
+    let mut back = front.clone();
+    let mut min_separation = f32::MAX;
+    let mut min_return_distance = f32::MAX;
+
+    for step in 0..config.horizon_steps {
+        Simulation::<V>::integrate_step(&front, &mut back, config.dt, config.g);
+        std::mem::swap(&mut front, &mut back);
+
+        for i in 0..front.len() {
+            for j in (i + 1)..front.len() {
+                let separation = (front[j].pos - front[i].pos).length();
+                min_separation = min_separation.min(separation);
+            }
+        }
+
+        let max_radius = front
+            .iter()
+            .fold(0.0_f32, |max, body| max.max(body.pos.length()));
+        if max_radius > config.bounding_radius {
+            return f32::MAX; // ejected: not a periodic orbit
+        }
+
+        if step >= config.settle_steps {
+            let distance = phase_distance(&s0, &front);
+            min_return_distance = min_return_distance.min(distance);
+        }
+    }
+
+    if min_separation < SOFTENING_LENGTH {
+        min_return_distance + CLOSE_ENCOUNTER_PENALTY
+    } else {
+        min_return_distance
+    }
+}
+
+fn random_params(rng: &mut impl Rng) -> [f32; PARAM_COUNT] {
+    // Layout matches params_to_bodies: [pos0, vel0, pos1, vel1].
+    let mut params = [0.0; PARAM_COUNT];
+    for p in &mut params[0..2] {
+        *p = rng.gen_range(-150.0..150.0);
+    }
+    for p in &mut params[2..4] {
+        *p = rng.gen_range(-1.0..1.0);
+    }
+    for p in &mut params[4..6] {
+        *p = rng.gen_range(-150.0..150.0);
+    }
+    for p in &mut params[6..8] {
+        *p = rng.gen_range(-1.0..1.0);
+    }
+    params
+}
+
+/// Samples a standard-normal value via Box-Muller.
+fn gaussian(rng: &mut impl Rng) -> f32 {
+    let u1: f32 = rng.gen_range(f32::EPSILON..1.0);
+    let u2: f32 = rng.gen_range(0.0..1.0);
+    (-2.0 * u1.ln()).sqrt() * (std::f32::consts::TAU * u2).cos()
+}
+
+fn perturb(params: &[f32; PARAM_COUNT], sigma: f32, rng: &mut impl Rng) -> [f32; PARAM_COUNT] {
+    let mut next = *params;
+    for p in &mut next {
+        *p += gaussian(rng) * sigma;
+    }
+    next
+}
+
+/// Hill-climbs from several random seeds, perturbing each restart's current
+/// best with shrinking Gaussian noise and keeping only improvements.
+pub fn hill_climb<V: SimVec>(config: &SearchConfig, iterations: u32) -> SearchResult<V> {
+    let mut rng = rand::thread_rng();
+
+    let mut global_best_params = random_params(&mut rng);
+    let mut global_best_score = f32::MAX;
+
+    let iterations_per_restart = (iterations / RESTART_COUNT).max(1);
+    for _ in 0..RESTART_COUNT {
+        let mut best_params = random_params(&mut rng);
+        let mut best_score = score::<V>(&best_params, config);
+
+        let mut sigma = INITIAL_SIGMA;
+        for _ in 0..iterations_per_restart {
+            let candidate = perturb(&best_params, sigma, &mut rng);
+            let candidate_score = score::<V>(&candidate, config);
+            if candidate_score < best_score {
+                best_score = candidate_score;
+                best_params = candidate;
+            }
+            sigma *= SIGMA_SHRINK;
+        }
+
+        if best_score < global_best_score {
+            global_best_score = best_score;
+            global_best_params = best_params;
+        }
+    }
+
+    SearchResult {
+        bodies: params_to_bodies(&global_best_params),
+        score: global_best_score,
+    }
+}